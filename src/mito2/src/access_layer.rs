@@ -12,8 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 
+use common_time::util::current_time_millis;
 use object_store::services::Fs;
 use object_store::util::{join_dir, with_instrument_layers};
 use object_store::ObjectStore;
@@ -22,15 +25,20 @@ use store_api::metadata::RegionMetadataRef;
 
 use crate::cache::write_cache::SstUploadRequest;
 use crate::cache::CacheManagerRef;
-use crate::error::{CleanDirSnafu, DeleteIndexSnafu, DeleteSstSnafu, OpenDalSnafu, Result};
+use crate::error::{
+    CleanDirSnafu, DeleteIndexSnafu, DeleteSstSnafu, InsecureDataDirSnafu, OpenDalSnafu, Result,
+};
 use crate::read::Source;
 use crate::sst::file::{FileHandle, FileId, FileMeta};
+use crate::sst::footer;
 use crate::sst::index::intermediate::IntermediateManager;
 use crate::sst::index::IndexerBuilder;
 use crate::sst::location;
 use crate::sst::parquet::reader::ParquetReaderBuilder;
 use crate::sst::parquet::writer::ParquetWriter;
 use crate::sst::parquet::{SstInfo, WriteOptions};
+use crate::sst::snapshot::{self, SnapshotManifest};
+use crate::sst::storage_registry::StorageRegistryRef;
 
 pub type AccessLayerRef = Arc<AccessLayer>;
 
@@ -41,6 +49,10 @@ pub struct AccessLayer {
     object_store: ObjectStore,
     /// Intermediate manager for inverted index.
     intermediate_manager: IntermediateManager,
+    /// Named/URL-addressable object store backends that a
+    /// [`SstWriteRequest::storage`] value can select instead of
+    /// `object_store`.
+    storage_registry: StorageRegistryRef,
 }
 
 impl std::fmt::Debug for AccessLayer {
@@ -57,14 +69,24 @@ impl AccessLayer {
         region_dir: impl Into<String>,
         object_store: ObjectStore,
         intermediate_manager: IntermediateManager,
+        storage_registry: StorageRegistryRef,
     ) -> AccessLayer {
         AccessLayer {
             region_dir: region_dir.into(),
             object_store,
             intermediate_manager,
+            storage_registry,
         }
     }
 
+    /// Resolves the object store `write_sst`/`read_sst`/`delete_sst` should
+    /// use for `storage`, falling back to the layer's default
+    /// `object_store` when `storage` is `None`.
+    fn resolve_object_store(&self, storage: Option<&str>) -> Result<ObjectStore> {
+        self.storage_registry
+            .resolve(storage, &self.object_store)
+    }
+
     /// Returns the directory of the region.
     pub fn region_dir(&self) -> &str {
         &self.region_dir
@@ -77,17 +99,16 @@ impl AccessLayer {
 
     /// Deletes a SST file (and its index file if it has one) with given file id.
     pub(crate) async fn delete_sst(&self, file_meta: &FileMeta) -> Result<()> {
+        let object_store = self.resolve_object_store(file_meta.storage.as_deref())?;
+
         let path = location::sst_file_path(&self.region_dir, file_meta.file_id);
-        self.object_store
-            .delete(&path)
-            .await
-            .context(DeleteSstSnafu {
-                file_id: file_meta.file_id,
-            })?;
+        object_store.delete(&path).await.context(DeleteSstSnafu {
+            file_id: file_meta.file_id,
+        })?;
 
         if file_meta.inverted_index_available() {
             let path = location::index_file_path(&self.region_dir, file_meta.file_id);
-            self.object_store
+            object_store
                 .delete(&path)
                 .await
                 .context(DeleteIndexSnafu {
@@ -99,23 +120,63 @@ impl AccessLayer {
     }
 
     /// Returns a reader builder for specific `file`.
-    pub(crate) fn read_sst(&self, file: FileHandle) -> ParquetReaderBuilder {
-        ParquetReaderBuilder::new(self.region_dir.clone(), file, self.object_store.clone())
+    ///
+    /// When `verify` is set, the SST's integrity footer (if present) is
+    /// checked before the builder is handed back, failing fast with
+    /// [`CorruptedSstSnafu`](crate::error::CorruptedSstSnafu) rather than
+    /// letting corrupt bytes reach the parquet decoder. SSTs written before
+    /// the footer subsystem existed have no footer and are treated as
+    /// "unverified" rather than corrupt.
+    pub(crate) async fn read_sst(
+        &self,
+        file: FileHandle,
+        verify: bool,
+    ) -> Result<ParquetReaderBuilder> {
+        if verify {
+            self.verify_sst(file.file_id(), file.meta_ref().storage.as_deref())
+                .await?;
+        }
+
+        let object_store = self.resolve_object_store(file.meta_ref().storage.as_deref())?;
+        Ok(ParquetReaderBuilder::new(
+            self.region_dir.clone(),
+            file,
+            object_store,
+        ))
+    }
+
+    /// Verifies the integrity footer of the SST file for `file_id`, stored on
+    /// the backend selected by `storage`, if one is present, failing fast
+    /// with [`CorruptedSstSnafu`](crate::error::CorruptedSstSnafu).
+    ///
+    /// SSTs written before the integrity footer subsystem existed have no
+    /// footer and are treated as "unverified" rather than corrupt, so this
+    /// returns `Ok(false)` for them instead of an error.
+    pub(crate) async fn verify_sst(&self, file_id: FileId, storage: Option<&str>) -> Result<bool> {
+        let object_store = self.resolve_object_store(storage)?;
+        let path = location::sst_file_path(&self.region_dir, file_id);
+        let (_payload, verified) = footer::read_and_verify(&object_store, &path, file_id).await?;
+        Ok(verified)
     }
 
     /// Writes a SST with specific `file_id` and `metadata` to the layer.
     ///
-    /// Returns the info of the SST. If no data written, returns None.
+    /// Returns the info of the SST plus its integrity footer checksum. If no
+    /// data written, returns `None`.
     pub(crate) async fn write_sst(
         &self,
         request: SstWriteRequest,
         write_opts: &WriteOptions,
-    ) -> Result<Option<SstInfo>> {
+    ) -> Result<Option<WrittenSst>> {
         let file_path = location::sst_file_path(&self.region_dir, request.file_id);
         let index_file_path = location::index_file_path(&self.region_dir, request.file_id);
         let region_id = request.metadata.region_id;
         let file_id = request.file_id;
         let cache_manager = request.cache_manager.clone();
+        // Resolve the target backend for this write, letting `request.storage`
+        // route cold/hot regions to different object stores while the write
+        // cache (if enabled) still fronts whichever one is picked.
+        let object_store = self.resolve_object_store(request.storage.as_deref())?;
 
         let sst_info = if let Some(write_cache) = cache_manager.write_cache() {
             // Write to the write cache.
@@ -123,9 +184,9 @@ impl AccessLayer {
                 .write_and_upload_sst(
                     request,
                     SstUploadRequest {
-                        upload_path: file_path,
-                        index_upload_path: index_file_path,
-                        remote_store: self.object_store.clone(),
+                        upload_path: file_path.clone(),
+                        index_upload_path: index_file_path.clone(),
+                        remote_store: object_store.clone(),
                     },
                     write_opts,
                 )
@@ -137,19 +198,15 @@ impl AccessLayer {
                 mem_threshold_index_create: request.mem_threshold_index_create,
                 write_buffer_size: request.index_write_buffer_size,
                 file_id,
-                file_path: index_file_path,
+                file_path: index_file_path.clone(),
                 metadata: &request.metadata,
                 row_group_size: write_opts.row_group_size,
-                object_store: self.object_store.clone(),
+                object_store: object_store.clone(),
                 intermediate_manager: self.intermediate_manager.clone(),
             }
             .build();
-            let mut writer = ParquetWriter::new(
-                file_path,
-                request.metadata,
-                self.object_store.clone(),
-                indexer,
-            );
+            let mut writer =
+                ParquetWriter::new(file_path.clone(), request.metadata, object_store.clone(), indexer);
             writer.write_all(request.source, write_opts).await?
         };
 
@@ -160,16 +217,174 @@ impl AccessLayer {
             }
         }
 
-        Ok(sst_info)
+        let Some(sst_info) = sst_info else {
+            return Ok(None);
+        };
+
+        // Only write the integrity footer sidecar once the writer/indexer
+        // have successfully flushed, so a failed write never leaves a
+        // footer pointing at a half-written object.
+        let checksum = footer::finalize_object(&object_store, &file_path).await?;
+        if object_store
+            .is_exist(&index_file_path)
+            .await
+            .context(OpenDalSnafu)?
+        {
+            footer::finalize_object(&object_store, &index_file_path).await?;
+        }
+
+        Ok(Some(WrittenSst {
+            info: sst_info,
+            checksum,
+            footer_version: footer::footer_version(),
+        }))
     }
     /// Returns whether the file exists in the object store.
     pub(crate) async fn is_exist(&self, file_meta: &FileMeta) -> Result<bool> {
+        let object_store = self.resolve_object_store(file_meta.storage.as_deref())?;
         let path = location::sst_file_path(&self.region_dir, file_meta.file_id);
-        self.object_store
-            .is_exist(&path)
-            .await
-            .context(OpenDalSnafu)
+        object_store.is_exist(&path).await.context(OpenDalSnafu)
     }
+
+    /// Sweeps SST and index files under `region_dir` that are not referenced
+    /// by `live` (i.e. orphaned by a failed flush/compaction or a crash).
+    ///
+    /// A file is only reaped once it has sat untouched for `grace_period`, so
+    /// in-flight writes to the atomic `.tmp` dir and freshly uploaded but
+    /// not-yet-committed SSTs are never touched. The sweep is idempotent:
+    /// running it again over the same `live` set is always safe.
+    ///
+    /// Sweeps the default `object_store` as well as every backend named in
+    /// the layer's `storage_registry`, since `chunk0-4` lets individual
+    /// files live on a non-default `storage` and an orphan there would
+    /// otherwise never be reaped.
+    pub(crate) async fn gc(
+        &self,
+        live: &HashSet<FileId>,
+        grace_period: Duration,
+    ) -> Result<GcReport> {
+        let mut report = GcReport::default();
+        for object_store in std::iter::once(&self.object_store)
+            .chain(self.storage_registry.named_backends())
+        {
+            report.merge(gc_backend(object_store, &self.region_dir, live, grace_period).await?);
+        }
+        Ok(report)
+    }
+
+    /// Copies a consistent point-in-time snapshot of `files` (a manifest
+    /// version's SST + index set) into `dest_dir` under `dest`, for backup
+    /// or cloning without stopping writes.
+    ///
+    /// Because SSTs are immutable once committed, pinning `files` at call
+    /// time is the only consistency requirement; newer flushes/compactions
+    /// racing with the copy simply aren't included. The copy is resumable:
+    /// re-running it skips destination objects that already exist with a
+    /// matching checksum.
+    ///
+    /// Each file's actual backend is resolved the same way
+    /// `read_sst`/`delete_sst` do, so files routed to an alternate
+    /// `storage` by the registry are copied from the right place.
+    pub(crate) async fn snapshot(
+        &self,
+        region_id: store_api::storage::RegionId,
+        source_version: u64,
+        files: &[FileMeta],
+        dest: &ObjectStore,
+        dest_dir: &str,
+    ) -> Result<SnapshotManifest> {
+        snapshot::snapshot(
+            region_id,
+            source_version,
+            &self.region_dir,
+            &self.object_store,
+            &self.storage_registry,
+            files,
+            dest,
+            dest_dir,
+        )
+        .await
+    }
+}
+
+/// Default grace period a file must sit untouched before [`AccessLayer::gc`]
+/// will reclaim it, guarding against races with in-flight writes. Callers
+/// that need a different value (e.g. from the datanode config) pass their
+/// own `grace_period` to `gc` instead of relying on this default.
+pub(crate) const DEFAULT_GC_GRACE_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+/// Summary of a [`AccessLayer::gc`] sweep.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct GcReport {
+    /// Number of orphaned SST/index files removed.
+    pub(crate) removed_files: u64,
+    /// Total bytes freed by the sweep.
+    pub(crate) freed_bytes: u64,
+}
+
+impl GcReport {
+    /// Accumulates the counters of another sweep (e.g. over a different
+    /// backend) into this one.
+    fn merge(&mut self, other: GcReport) {
+        self.removed_files += other.removed_files;
+        self.freed_bytes += other.freed_bytes;
+    }
+}
+
+/// Sweeps a single `object_store` under `region_dir` for SST/index files not
+/// referenced by `live`, per the same rules as [`AccessLayer::gc`]. Factored
+/// out of the method so it can be swept once per backend and unit tested
+/// without needing a full [`AccessLayer`].
+async fn gc_backend(
+    object_store: &ObjectStore,
+    region_dir: &str,
+    live: &HashSet<FileId>,
+    grace_period: Duration,
+) -> Result<GcReport> {
+    let now_millis = current_time_millis();
+    let mut report = GcReport::default();
+
+    let entries = object_store.list(region_dir).await.context(OpenDalSnafu)?;
+    for entry in entries {
+        let path = entry.path();
+        let Some(file_id) = location::parse_file_id_from_path(path) else {
+            // Not a recognizable SST/index object (e.g. the `.tmp` dir, or a
+            // footer sidecar, which is reaped alongside its object below
+            // instead of on its own); skip it.
+            continue;
+        };
+        if live.contains(&file_id) {
+            continue;
+        }
+
+        let metadata = entry.metadata();
+        let age_millis = metadata
+            .last_modified()
+            .map(|modified| now_millis.saturating_sub(modified.timestamp_millis()))
+            .unwrap_or(0);
+        if age_millis < grace_period.as_millis() as i64 {
+            // Too young: could be an in-flight write, don't touch it yet.
+            continue;
+        }
+
+        let size = metadata.content_length();
+        object_store.delete(path).await.context(OpenDalSnafu)?;
+        footer::delete_footer(object_store, path).await?;
+        report.freed_bytes += size;
+        report.removed_files += 1;
+    }
+
+    Ok(report)
+}
+
+/// Result of [`AccessLayer::write_sst`]: the usual SST info plus the
+/// integrity footer checksum/version that was written to the footer sidecar
+/// next to each uploaded object, for the caller to persist onto the resulting
+/// [`FileMeta::checksum`]/[`FileMeta::footer_version`].
+pub(crate) struct WrittenSst {
+    pub(crate) info: SstInfo,
+    pub(crate) checksum: u64,
+    pub(crate) footer_version: u8,
 }
 
 /// Contents to build a SST.
@@ -178,7 +393,9 @@ pub(crate) struct SstWriteRequest {
     pub(crate) metadata: RegionMetadataRef,
     pub(crate) source: Source,
     pub(crate) cache_manager: CacheManagerRef,
-    #[allow(dead_code)]
+    /// Name of a registered backend, or a `scheme://...` URL, selecting an
+    /// alternate [`ObjectStore`] for this write/read/delete instead of the
+    /// layer's default `object_store`. See [`StorageRegistry`](crate::sst::storage_registry::StorageRegistry).
     pub(crate) storage: Option<String>,
     /// Whether to create inverted index.
     pub(crate) create_inverted_index: bool,
@@ -188,10 +405,33 @@ pub(crate) struct SstWriteRequest {
     pub(crate) index_write_buffer_size: Option<usize>,
 }
 
+/// Environment variable that downgrades a failed permission check (see
+/// [`check_data_dir_permission`]) to a warning, for containerized setups that
+/// run as root with a permissive umask. This is the fallback the
+/// `storage.fs.disable_permission_checks` datanode config flag uses when
+/// unset; see `disable_permission_checks` on [`new_fs_object_store`].
+const DISABLE_PERMISSION_CHECKS_ENV: &str = "GREPTIMEDB_FS_DISABLE_PERMISSION_CHECKS";
+
 /// Creates a fs object store with atomic write dir.
-pub(crate) async fn new_fs_object_store(root: &str) -> Result<ObjectStore> {
+///
+/// `disable_permission_checks` mirrors the `storage.fs.disable_permission_checks`
+/// datanode config flag; when unset, the [`DISABLE_PERMISSION_CHECKS_ENV`]
+/// environment variable is also consulted.
+pub(crate) async fn new_fs_object_store(
+    root: &str,
+    disable_permission_checks: bool,
+) -> Result<ObjectStore> {
+    let downgrade_to_warning = disable_permission_checks || permission_checks_disabled_by_env();
+    check_data_dir_permission(root, downgrade_to_warning)?;
+
     let atomic_write_dir = join_dir(root, ".tmp/");
     clean_dir(&atomic_write_dir).await?;
+    tokio::fs::create_dir_all(&atomic_write_dir)
+        .await
+        .context(CleanDirSnafu {
+            dir: &atomic_write_dir,
+        })?;
+    secure_dir(&atomic_write_dir, downgrade_to_warning)?;
 
     let mut builder = Fs::default();
     builder.root(root).atomic_write_dir(&atomic_write_dir);
@@ -202,6 +442,88 @@ pub(crate) async fn new_fs_object_store(root: &str) -> Result<ObjectStore> {
     Ok(object_store)
 }
 
+/// Returns whether the [`DISABLE_PERMISSION_CHECKS_ENV`] escape hatch is set.
+fn permission_checks_disabled_by_env() -> bool {
+    std::env::var(DISABLE_PERMISSION_CHECKS_ENV)
+        .map(|v| v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Walks `root` and its parents and refuses to start if any component is
+/// writable by group or other without the sticky bit set, unless
+/// `downgrade_to_warning` is set.
+///
+/// Ownership alone is not flagged: it is standard practice to run the
+/// datanode as a non-root service account under root-owned parent
+/// directories (e.g. `/`, `/var`, `/data`), and that is safe as long as
+/// those directories aren't group/other-writable. Directories like `/tmp`
+/// (mode `1777`) are deliberately world-writable but safe because the
+/// sticky bit restricts deletion/renaming to the file's owner, so they are
+/// exempted too.
+#[cfg(unix)]
+fn check_data_dir_permission(root: &str, downgrade_to_warning: bool) -> Result<()> {
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+
+    const STICKY_BIT: u32 = 0o1000;
+
+    for ancestor in Path::new(root).ancestors() {
+        let Ok(metadata) = std::fs::metadata(ancestor) else {
+            // Parent may not exist yet (it will be created by the object store).
+            continue;
+        };
+        let mode = metadata.mode();
+        let group_or_world_writable = mode & 0o022 != 0;
+        let has_sticky_bit = mode & STICKY_BIT != 0;
+        if group_or_world_writable && !has_sticky_bit {
+            if downgrade_to_warning {
+                common_telemetry::warn!(
+                    "Data directory {:?} has insecure permissions (mode {:o}); \
+                     continuing because permission checks are disabled",
+                    ancestor,
+                    mode & 0o777,
+                );
+                continue;
+            }
+            return InsecureDataDirSnafu {
+                path: ancestor.display().to_string(),
+                mode: mode & 0o777,
+            }
+            .fail();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_data_dir_permission(_root: &str, _downgrade_to_warning: bool) -> Result<()> {
+    Ok(())
+}
+
+/// Restricts the atomic-write `.tmp` dir to `0700` so other local users can't
+/// observe or tamper with in-flight uploads.
+///
+/// Must run after the dir is created (e.g. via `create_dir_all`): `.tmp` is
+/// only ever removed by [`clean_dir`], never created by it, and the `Fs`
+/// object store builder doesn't create it eagerly either.
+#[cfg(unix)]
+fn secure_dir(dir: &str, downgrade_to_warning: bool) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Err(e) = std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)) {
+        if !downgrade_to_warning {
+            return Err(e).context(CleanDirSnafu { dir });
+        }
+        common_telemetry::warn!(e; "Failed to restrict permissions of atomic-write dir {dir}");
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn secure_dir(_dir: &str, _downgrade_to_warning: bool) -> Result<()> {
+    Ok(())
+}
+
 /// Clean the directory.
 async fn clean_dir(dir: &str) -> Result<()> {
     if tokio::fs::try_exists(dir)
@@ -215,3 +537,86 @@ async fn clean_dir(dir: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use object_store::services::Memory;
+
+    use super::*;
+
+    fn new_memory_store() -> ObjectStore {
+        ObjectStore::new(Memory::default()).unwrap().finish()
+    }
+
+    #[tokio::test]
+    async fn test_gc_removes_orphan_but_keeps_live() {
+        let store = new_memory_store();
+        let live_id = FileId::random();
+        let orphan_id = FileId::random();
+        store
+            .write(&location::sst_file_path("region/", live_id), vec![0u8; 4])
+            .await
+            .unwrap();
+        store
+            .write(&location::sst_file_path("region/", orphan_id), vec![0u8; 8])
+            .await
+            .unwrap();
+
+        let live = HashSet::from([live_id]);
+        let report = gc_backend(&store, "region/", &live, Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(report.removed_files, 1);
+        assert_eq!(report.freed_bytes, 8);
+        assert!(store
+            .is_exist(&location::sst_file_path("region/", live_id))
+            .await
+            .unwrap());
+        assert!(!store
+            .is_exist(&location::sst_file_path("region/", orphan_id))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_gc_respects_grace_period() {
+        let store = new_memory_store();
+        let orphan_id = FileId::random();
+        store
+            .write(&location::sst_file_path("region/", orphan_id), vec![0u8; 4])
+            .await
+            .unwrap();
+
+        let report = gc_backend(
+            &store,
+            "region/",
+            &HashSet::new(),
+            Duration::from_secs(3600),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(report.removed_files, 0);
+        assert!(store
+            .is_exist(&location::sst_file_path("region/", orphan_id))
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_gc_removes_footer_sidecar_with_its_object() {
+        let store = new_memory_store();
+        let orphan_id = FileId::random();
+        let path = location::sst_file_path("region/", orphan_id);
+        store.write(&path, vec![0u8; 4]).await.unwrap();
+        footer::finalize_object(&store, &path).await.unwrap();
+
+        gc_backend(&store, "region/", &HashSet::new(), Duration::ZERO)
+            .await
+            .unwrap();
+
+        assert!(!store.is_exist(&path).await.unwrap());
+        assert!(!store.is_exist(&format!("{path}.footer")).await.unwrap());
+    }
+}
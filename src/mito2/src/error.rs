@@ -0,0 +1,91 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::{Location, Snafu};
+
+use crate::sst::file::FileId;
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum Error {
+    #[snafu(display("Failed to clean dir {dir}"))]
+    CleanDir {
+        dir: String,
+        #[snafu(source)]
+        error: std::io::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to delete SST file, file_id: {file_id}"))]
+    DeleteSst {
+        file_id: FileId,
+        #[snafu(source)]
+        error: object_store::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to delete index file, file_id: {file_id}"))]
+    DeleteIndex {
+        file_id: FileId,
+        #[snafu(source)]
+        error: object_store::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Operation on object store failed"))]
+    OpenDal {
+        #[snafu(source)]
+        error: object_store::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display(
+        "SST file is corrupted, file_id: {file_id}, expected checksum: {expected}, actual: {actual}"
+    ))]
+    CorruptedSst {
+        file_id: FileId,
+        expected: u64,
+        actual: u64,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Data directory {path:?} has insecure permissions (mode {mode:o})"))]
+    InsecureDataDir {
+        path: String,
+        mode: u32,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid storage URL: {url}"))]
+    InvalidStorageUrl {
+        url: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Unknown storage backend: {storage}"))]
+    UnknownStorage {
+        storage: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -0,0 +1,99 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SST file identity and metadata.
+
+use std::fmt;
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+/// Unique identifier of a SST/index file pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FileId(Uuid);
+
+impl FileId {
+    /// Generates a new, random [FileId].
+    pub fn random() -> FileId {
+        FileId(Uuid::new_v4())
+    }
+
+    /// Parses a [FileId] from its canonical string representation.
+    pub fn parse_str(input: &str) -> Result<FileId, uuid::Error> {
+        Uuid::parse_str(input).map(FileId)
+    }
+}
+
+impl fmt::Display for FileId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Metadata of a SST file tracked by the manifest.
+#[derive(Debug, Clone)]
+pub struct FileMeta {
+    pub file_id: FileId,
+    /// Whether an inverted index file was built alongside the SST.
+    pub available_indexes: AvailableIndexes,
+    /// Name of a registered backend, or a `scheme://...` URL, that this
+    /// file's SST/index objects live under, instead of the region's
+    /// default `object_store`. See
+    /// [`StorageRegistry`](crate::sst::storage_registry::StorageRegistry).
+    pub storage: Option<String>,
+    /// xxHash64 checksum of the SST payload, recorded by the integrity
+    /// footer subsystem. `None` for files written before that subsystem
+    /// existed.
+    pub checksum: Option<u64>,
+    /// Format version of the integrity footer the checksum was computed
+    /// under, if any.
+    pub footer_version: Option<u8>,
+}
+
+impl FileMeta {
+    /// Returns whether this file has an inverted index built for it.
+    pub fn inverted_index_available(&self) -> bool {
+        matches!(self.available_indexes, AvailableIndexes::Inverted)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AvailableIndexes {
+    None,
+    Inverted,
+}
+
+/// A cheaply cloneable handle to a [FileMeta].
+#[derive(Debug, Clone)]
+pub struct FileHandle {
+    meta: Arc<FileMeta>,
+}
+
+impl FileHandle {
+    pub fn new(meta: FileMeta) -> FileHandle {
+        FileHandle {
+            meta: Arc::new(meta),
+        }
+    }
+
+    /// Returns the file id of the handle.
+    pub fn file_id(&self) -> FileId {
+        self.meta.file_id
+    }
+
+    /// Returns a reference to the underlying [FileMeta].
+    pub fn meta_ref(&self) -> &FileMeta {
+        &self.meta
+    }
+}
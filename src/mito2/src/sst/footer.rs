@@ -0,0 +1,241 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small, fixed-size integrity footer stored alongside (not inside) SST and
+//! index objects so that silent corruption on remote object stores can be
+//! detected before the bytes reach the parquet/puffin decoders.
+//!
+//! The footer is written to a sidecar object (`<path>.footer`) rather than
+//! appended to the SST/index payload itself: appending would corrupt the
+//! parquet/puffin trailer that readers expect to find at the very end of the
+//! file, breaking every write-then-read round trip.
+
+use snafu::{ensure, ResultExt};
+
+use crate::error::{CorruptedSstSnafu, OpenDalSnafu, Result};
+use crate::sst::file::FileId;
+
+/// Magic bytes identifying a footer written by this subsystem.
+const FOOTER_MAGIC: [u8; 4] = *b"GTIF";
+
+/// Current footer format version.
+const FOOTER_VERSION: u8 = 1;
+
+/// `magic(4) + version(1) + payload_len(8) + checksum(8)`.
+pub(crate) const FOOTER_SIZE: u64 = 4 + 1 + 8 + 8;
+
+/// Integrity footer describing a SST or index object's payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegrityFooter {
+    /// Length of the payload the footer was computed over.
+    pub payload_len: u64,
+    /// xxHash64 checksum of the payload.
+    pub checksum: u64,
+}
+
+impl IntegrityFooter {
+    /// Computes a footer for `payload`.
+    pub fn compute(payload: &[u8]) -> IntegrityFooter {
+        IntegrityFooter {
+            payload_len: payload.len() as u64,
+            checksum: xxhash_checksum(payload),
+        }
+    }
+
+    /// Encodes the footer to its on-disk representation.
+    pub fn to_bytes(self) -> [u8; FOOTER_SIZE as usize] {
+        let mut buf = [0u8; FOOTER_SIZE as usize];
+        buf[0..4].copy_from_slice(&FOOTER_MAGIC);
+        buf[4] = FOOTER_VERSION;
+        buf[5..13].copy_from_slice(&self.payload_len.to_le_bytes());
+        buf[13..21].copy_from_slice(&self.checksum.to_le_bytes());
+        buf
+    }
+
+    /// Tries to decode a footer from the bytes of a footer sidecar object.
+    ///
+    /// Returns `Ok(None)` (rather than an error) when the magic is absent so
+    /// that objects written before this subsystem existed are treated as
+    /// "unverified" instead of corrupt.
+    pub fn try_decode(bytes: &[u8]) -> Result<Option<IntegrityFooter>> {
+        if bytes.len() != FOOTER_SIZE as usize {
+            return Ok(None);
+        }
+        if bytes[0..4] != FOOTER_MAGIC {
+            return Ok(None);
+        }
+        if bytes[4] != FOOTER_VERSION {
+            // Unknown future version: treat as unverified rather than corrupt.
+            return Ok(None);
+        }
+        let payload_len = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+        let checksum = u64::from_le_bytes(bytes[13..21].try_into().unwrap());
+        Ok(Some(IntegrityFooter {
+            payload_len,
+            checksum,
+        }))
+    }
+
+    /// Verifies `payload` against this footer, failing fast with
+    /// [`CorruptedSstSnafu`] on mismatch.
+    pub fn verify(&self, file_id: FileId, payload: &[u8]) -> Result<()> {
+        let actual = xxhash_checksum(payload);
+        ensure!(
+            payload.len() as u64 == self.payload_len && actual == self.checksum,
+            CorruptedSstSnafu {
+                file_id,
+                expected: self.checksum,
+                actual,
+            }
+        );
+        Ok(())
+    }
+}
+
+/// Current footer format version, for callers that persist it alongside the
+/// checksum (e.g. [`FileMeta::footer_version`](crate::sst::file::FileMeta)).
+pub fn footer_version() -> u8 {
+    FOOTER_VERSION
+}
+
+/// Returns the path of the footer sidecar object for `path`.
+fn footer_sidecar_path(path: &str) -> String {
+    format!("{path}.footer")
+}
+
+fn xxhash_checksum(payload: &[u8]) -> u64 {
+    use std::hash::Hasher;
+    use twox_hash::XxHash64;
+
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(payload);
+    hasher.finish()
+}
+
+/// Writes an integrity footer for the object already written at `path`, to a
+/// sidecar object next to it. Must only be called after the writer/indexer
+/// has successfully flushed `path`, and leaves `path` itself untouched so the
+/// parquet/puffin decoder never has to know the footer subsystem exists.
+///
+/// Returns the checksum recorded in the footer.
+pub async fn finalize_object(
+    object_store: &object_store::ObjectStore,
+    path: &str,
+) -> Result<u64> {
+    let payload = object_store.read(path).await.context(OpenDalSnafu)?.to_bytes();
+    let footer = IntegrityFooter::compute(&payload);
+
+    object_store
+        .write(&footer_sidecar_path(path), footer.to_bytes().to_vec())
+        .await
+        .context(OpenDalSnafu)?;
+    Ok(footer.checksum)
+}
+
+/// Copies the footer sidecar for `src_path`, if one exists, from `source` to
+/// `dest` at the sidecar path for `dst_path`. A missing sidecar (e.g. an SST
+/// written before this subsystem existed) is not an error; the destination
+/// simply ends up unverified too.
+pub async fn copy_footer(
+    source: &object_store::ObjectStore,
+    src_path: &str,
+    dest: &object_store::ObjectStore,
+    dst_path: &str,
+) -> Result<()> {
+    let src_footer_path = footer_sidecar_path(src_path);
+    if !source.is_exist(&src_footer_path).await.context(OpenDalSnafu)? {
+        return Ok(());
+    }
+    let bytes = source
+        .read(&src_footer_path)
+        .await
+        .context(OpenDalSnafu)?
+        .to_bytes();
+    dest.write(&footer_sidecar_path(dst_path), bytes)
+        .await
+        .context(OpenDalSnafu)?;
+    Ok(())
+}
+
+/// Deletes the footer sidecar for `path`, if one exists. Not finding one is
+/// not an error, since SSTs written before this subsystem existed never had
+/// one.
+pub async fn delete_footer(object_store: &object_store::ObjectStore, path: &str) -> Result<()> {
+    let footer_path = footer_sidecar_path(path);
+    if object_store.is_exist(&footer_path).await.context(OpenDalSnafu)? {
+        object_store
+            .delete(&footer_path)
+            .await
+            .context(OpenDalSnafu)?;
+    }
+    Ok(())
+}
+
+/// Reads the object at `path` from `object_store` and verifies it against its
+/// footer sidecar, if one exists. Objects without a sidecar are returned with
+/// `verified = false` rather than an error, for backward compatibility with
+/// SSTs written before this subsystem existed.
+pub async fn read_and_verify(
+    object_store: &object_store::ObjectStore,
+    path: &str,
+    file_id: FileId,
+) -> Result<(bytes::Bytes, bool)> {
+    let payload = object_store.read(path).await.context(OpenDalSnafu)?.to_bytes();
+
+    let footer_path = footer_sidecar_path(path);
+    if !object_store.is_exist(&footer_path).await.context(OpenDalSnafu)? {
+        // No footer sidecar: unverified, not corrupt.
+        return Ok((payload, false));
+    }
+    let footer_bytes = object_store
+        .read(&footer_path)
+        .await
+        .context(OpenDalSnafu)?
+        .to_bytes();
+    let Some(footer) = IntegrityFooter::try_decode(&footer_bytes)? else {
+        // Unrecognized sidecar format: unverified, not corrupt.
+        return Ok((payload, false));
+    };
+
+    footer.verify(file_id, &payload)?;
+    Ok((payload, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_footer_roundtrip() {
+        let payload = b"hello greptimedb".to_vec();
+        let footer = IntegrityFooter::compute(&payload);
+        let encoded = footer.to_bytes();
+        let decoded = IntegrityFooter::try_decode(&encoded).unwrap().unwrap();
+        assert_eq!(footer, decoded);
+    }
+
+    #[test]
+    fn test_footer_absent_is_unverified() {
+        let payload = b"no footer here".to_vec();
+        assert_eq!(IntegrityFooter::try_decode(&payload).unwrap(), None);
+    }
+
+    #[test]
+    fn test_footer_detects_corruption() {
+        let payload = b"important bytes".to_vec();
+        let footer = IntegrityFooter::compute(&payload);
+        let tampered = b"important ByTes".to_vec();
+        assert!(footer.verify(FileId::random(), &tampered).is_err());
+    }
+}
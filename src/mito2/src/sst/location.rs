@@ -0,0 +1,44 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers to derive object store paths for SST/index files from a
+//! `region_dir` and [FileId], and to go the other way round when listing
+//! a directory (e.g. for [`AccessLayer::gc`](crate::access_layer::AccessLayer::gc)).
+
+use crate::sst::file::FileId;
+
+const SST_EXTENSION: &str = "parquet";
+const INDEX_EXTENSION: &str = "puffin";
+
+/// Returns the path of the SST file for `file_id` under `region_dir`.
+pub fn sst_file_path(region_dir: &str, file_id: FileId) -> String {
+    format!("{region_dir}{file_id}.{SST_EXTENSION}")
+}
+
+/// Returns the path of the index file for `file_id` under `region_dir`.
+pub fn index_file_path(region_dir: &str, file_id: FileId) -> String {
+    format!("{region_dir}{file_id}.{INDEX_EXTENSION}")
+}
+
+/// Parses the [FileId] encoded in `path`, returning `None` if `path` is not
+/// a recognizable SST/index object path (e.g. it lives under the `.tmp`
+/// atomic-write dir, or carries an extension we don't own).
+pub fn parse_file_id_from_path(path: &str) -> Option<FileId> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let (stem, ext) = file_name.rsplit_once('.')?;
+    if ext != SST_EXTENSION && ext != INDEX_EXTENSION {
+        return None;
+    }
+    FileId::parse_str(stem).ok()
+}
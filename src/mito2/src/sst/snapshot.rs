@@ -0,0 +1,190 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Point-in-time export of a region's SST/index files to another object
+//! store, for backup and cloning purposes.
+//!
+//! SST files are immutable once committed, so the only consistency
+//! requirement for a snapshot is pinning the set of [`FileMeta`] at the
+//! moment the snapshot starts; the copy itself can run concurrently with
+//! ongoing flushes/compactions of newer files.
+
+use common_telemetry::debug;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use object_store::ObjectStore;
+use snafu::ResultExt;
+use store_api::storage::RegionId;
+
+use crate::error::{OpenDalSnafu, Result};
+use crate::sst::file::{FileId, FileMeta};
+use crate::sst::footer::{self, IntegrityFooter};
+use crate::sst::location;
+use crate::sst::storage_registry::StorageRegistry;
+
+/// Upper bound on the number of files copied concurrently by [`snapshot`].
+const SNAPSHOT_CONCURRENCY: usize = 8;
+
+/// Record of a single SST/index pair copied by [`snapshot`].
+#[derive(Debug, Clone)]
+pub struct SnapshotFileEntry {
+    pub file_id: FileId,
+    pub sst_size: u64,
+    pub index_size: Option<u64>,
+    /// xxHash64 checksum of the SST payload, for later validation.
+    pub checksum: u64,
+}
+
+/// Manifest describing the result of a [`snapshot`] call, sufficient to
+/// validate the copy and re-open it as a read-only region.
+#[derive(Debug, Clone)]
+pub struct SnapshotManifest {
+    pub region_id: RegionId,
+    /// Manifest version the snapshot was taken against.
+    pub source_version: u64,
+    pub files: Vec<SnapshotFileEntry>,
+}
+
+/// Copies the exact set of SST + index objects in `files` from `source`
+/// (resolving each file's actual backend through `storage_registry`, since
+/// `chunk0-4` lets individual files live on a non-default backend) to
+/// `dest_dir` in `dest`, preferring a server-side copy when the resolved
+/// source and `dest` share a backend and otherwise falling back to reading
+/// each object whole from the source and writing it to `dest`; objects are
+/// buffered in memory, not streamed.
+///
+/// Destination objects that already exist with a matching checksum are
+/// skipped, so a failed or interrupted snapshot can simply be re-run.
+pub(crate) async fn snapshot(
+    region_id: RegionId,
+    source_version: u64,
+    region_dir: &str,
+    default_source: &ObjectStore,
+    storage_registry: &StorageRegistry,
+    files: &[FileMeta],
+    dest: &ObjectStore,
+    dest_dir: &str,
+) -> Result<SnapshotManifest> {
+    let entries = stream::iter(files.iter().cloned().map(|file_meta| {
+        let source = storage_registry.resolve(file_meta.storage.as_deref(), default_source);
+        let dest = dest.clone();
+        let region_dir = region_dir.to_string();
+        let dest_dir = dest_dir.to_string();
+        async move {
+            let source = source?;
+            copy_one(&source, &region_dir, &file_meta, &dest, &dest_dir).await
+        }
+    }))
+    .buffer_unordered(SNAPSHOT_CONCURRENCY)
+    .try_collect::<Vec<_>>()
+    .await?;
+
+    Ok(SnapshotManifest {
+        region_id,
+        source_version,
+        files: entries,
+    })
+}
+
+async fn copy_one(
+    source: &ObjectStore,
+    region_dir: &str,
+    file_meta: &FileMeta,
+    dest: &ObjectStore,
+    dest_dir: &str,
+) -> Result<SnapshotFileEntry> {
+    let src_sst_path = location::sst_file_path(region_dir, file_meta.file_id);
+    let dst_sst_path = location::sst_file_path(dest_dir, file_meta.file_id);
+
+    let (sst_bytes, checksum) =
+        copy_object(source, &src_sst_path, dest, &dst_sst_path, file_meta.checksum).await?;
+    footer::copy_footer(source, &src_sst_path, dest, &dst_sst_path).await?;
+
+    let index_size = if file_meta.inverted_index_available() {
+        let src_index_path = location::index_file_path(region_dir, file_meta.file_id);
+        let dst_index_path = location::index_file_path(dest_dir, file_meta.file_id);
+        let (bytes, _checksum) =
+            copy_object(source, &src_index_path, dest, &dst_index_path, None).await?;
+        Some(bytes.len() as u64)
+    } else {
+        None
+    };
+
+    Ok(SnapshotFileEntry {
+        file_id: file_meta.file_id,
+        sst_size: sst_bytes.len() as u64,
+        index_size,
+        checksum,
+    })
+}
+
+/// Copies a single object, preferring a server-side copy and returning the
+/// copied bytes together with their checksum.
+///
+/// If `dest` already has an object at `dst_path`, it is only treated as a
+/// valid prior copy (and left untouched) when its checksum actually matches
+/// the source: `expected_checksum` when the caller already knows it (e.g.
+/// from the integrity footer recorded in `FileMeta`), or the freshly
+/// computed source checksum otherwise. A stale or partially-written
+/// destination object is overwritten, so snapshots stay resumable without
+/// risking a silently-corrupt "skip".
+async fn copy_object(
+    source: &ObjectStore,
+    src_path: &str,
+    dest: &ObjectStore,
+    dst_path: &str,
+    expected_checksum: Option<u64>,
+) -> Result<(bytes::Bytes, u64)> {
+    if dest.is_exist(dst_path).await.context(OpenDalSnafu)? {
+        let dest_bytes = dest.read(dst_path).await.context(OpenDalSnafu)?.to_bytes();
+        let dest_checksum = IntegrityFooter::compute(&dest_bytes).checksum;
+        let matches = match expected_checksum {
+            Some(expected) => expected == dest_checksum,
+            // No recorded checksum to compare against (e.g. an index file, or
+            // an SST written before the integrity footer subsystem existed):
+            // fall back to comparing against the source's own checksum.
+            None => {
+                let source_bytes = source.read(src_path).await.context(OpenDalSnafu)?.to_bytes();
+                IntegrityFooter::compute(&source_bytes).checksum == dest_checksum
+            }
+        };
+        if matches {
+            debug!("Snapshot destination {dst_path} already exists with a matching checksum, skipping copy");
+            return Ok((dest_bytes, dest_checksum));
+        }
+        debug!("Snapshot destination {dst_path} exists but checksum mismatches, overwriting");
+    }
+
+    // Prefer a server-side copy when source and dest are the same backend
+    // (same scheme, bucket/endpoint identity and root). `source.copy`
+    // resolves `dst_path` under the source's root, so the roots must match
+    // for the copy to land where `dest` reads it; the snapshot's separate
+    // location comes from `dest_dir` in `dst_path`.
+    if source.info().scheme() == dest.info().scheme()
+        && source.info().name() == dest.info().name()
+        && source.info().root() == dest.info().root()
+    {
+        if source.copy(src_path, dst_path).await.is_ok() {
+            let bytes = dest.read(dst_path).await.context(OpenDalSnafu)?.to_bytes();
+            let checksum = IntegrityFooter::compute(&bytes).checksum;
+            return Ok((bytes, checksum));
+        }
+    }
+
+    let bytes = source.read(src_path).await.context(OpenDalSnafu)?.to_bytes();
+    let checksum = IntegrityFooter::compute(&bytes).checksum;
+    dest.write(dst_path, bytes.clone())
+        .await
+        .context(OpenDalSnafu)?;
+    Ok((bytes, checksum))
+}
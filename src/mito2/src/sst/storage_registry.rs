@@ -0,0 +1,221 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resolves the `storage` value carried by a
+//! [`SstWriteRequest`](crate::access_layer::SstWriteRequest) to a concrete
+//! [`ObjectStore`], either by looking it up in a registry of named backends
+//! configured for the engine, or by parsing it as a `scheme://authority/path`
+//! URL and building a one-off backend on the fly.
+//!
+//! Each backend is gated behind a cargo feature so that builds can trim
+//! unused object store services; `storage-fs` and `storage-memory` are
+//! gated the same way, and a scheme whose feature is disabled fails to
+//! resolve as an unknown storage.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use object_store::ObjectStore;
+use snafu::OptionExt;
+
+use crate::error::{InvalidStorageUrlSnafu, Result, UnknownStorageSnafu};
+
+pub type StorageRegistryRef = Arc<StorageRegistry>;
+
+/// A registry of named, pre-configured object store backends that
+/// [`SstWriteRequest::storage`](crate::access_layer::SstWriteRequest::storage)
+/// can select between, in addition to ad-hoc backends built from a URL.
+///
+/// Backends built from a URL are cached by URL after the first resolution,
+/// since building one (connection pool, credentials resolution, ...) is
+/// expensive and `resolve` is called on hot paths like `read_sst`.
+#[derive(Default)]
+pub struct StorageRegistry {
+    named: HashMap<String, ObjectStore>,
+    by_url: Mutex<HashMap<String, ObjectStore>>,
+}
+
+impl std::fmt::Debug for StorageRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StorageRegistry")
+            .field("named", &self.named.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl StorageRegistry {
+    /// Creates a registry from a set of named backends, e.g. configured
+    /// under `[[storage.providers]]` in the datanode config.
+    pub fn new(named: HashMap<String, ObjectStore>) -> StorageRegistry {
+        StorageRegistry {
+            named,
+            by_url: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resolves `storage` to an [`ObjectStore`], returning `default` when
+    /// `storage` is `None`.
+    ///
+    /// `storage` is first looked up as a registry name; if that misses, it
+    /// is parsed as a URL whose scheme selects the backend service. URL
+    /// backends are cached so repeated resolutions of the same URL reuse
+    /// the same client instead of rebuilding one per call.
+    pub fn resolve(&self, storage: Option<&str>, default: &ObjectStore) -> Result<ObjectStore> {
+        let Some(storage) = storage else {
+            return Ok(default.clone());
+        };
+
+        if let Some(store) = self.named.get(storage) {
+            return Ok(store.clone());
+        }
+
+        if let Some(store) = self.by_url.lock().unwrap().get(storage) {
+            return Ok(store.clone());
+        }
+
+        let store = build_from_url(storage)?;
+        self.by_url
+            .lock()
+            .unwrap()
+            .insert(storage.to_string(), store.clone());
+        Ok(store)
+    }
+
+    /// Iterates over the registry's named backends, for callers (e.g.
+    /// [`AccessLayer::gc`](crate::access_layer::AccessLayer::gc)) that need
+    /// to sweep every backend a file might live on rather than resolve one
+    /// specific `storage` value. Ad-hoc URL-resolved backends are not
+    /// included, since they aren't a stable, enumerable set.
+    pub fn named_backends(&self) -> impl Iterator<Item = &ObjectStore> {
+        self.named.values()
+    }
+}
+
+/// Builds a one-off [`ObjectStore`] from a `scheme://authority/path` URL,
+/// where the scheme selects the OpenDAL service (`fs`, `s3`, `oss`, `gcs`,
+/// `memory`) and the authority/path supply the bucket/root.
+fn build_from_url(url: &str) -> Result<ObjectStore> {
+    let (scheme, rest) = url
+        .split_once("://")
+        .context(InvalidStorageUrlSnafu { url })?;
+
+    match scheme {
+        #[cfg(feature = "storage-memory")]
+        "memory" => Ok(build_memory()),
+        #[cfg(feature = "storage-fs")]
+        "fs" => Ok(build_fs(rest)?),
+        #[cfg(feature = "storage-s3")]
+        "s3" => Ok(build_s3(rest)?),
+        #[cfg(feature = "storage-oss")]
+        "oss" => Ok(build_oss(rest)?),
+        #[cfg(feature = "storage-gcs")]
+        "gcs" => Ok(build_gcs(rest)?),
+        _ => UnknownStorageSnafu { storage: url }.fail(),
+    }
+}
+
+#[cfg(feature = "storage-memory")]
+fn build_memory() -> ObjectStore {
+    use object_store::services::Memory;
+
+    ObjectStore::new(Memory::default())
+        .expect("memory backend never fails to build")
+        .finish()
+}
+
+#[cfg(feature = "storage-fs")]
+fn build_fs(root: &str) -> Result<ObjectStore> {
+    use object_store::services::Fs;
+    use snafu::ResultExt;
+
+    let mut builder = Fs::default();
+    builder.root(root);
+    Ok(ObjectStore::new(builder)
+        .context(crate::error::OpenDalSnafu)?
+        .finish())
+}
+
+#[cfg(feature = "storage-s3")]
+fn build_s3(authority_and_path: &str) -> Result<ObjectStore> {
+    use object_store::services::S3;
+    use snafu::ResultExt;
+
+    let (bucket, root) = authority_and_path
+        .split_once('/')
+        .unwrap_or((authority_and_path, ""));
+    let mut builder = S3::default();
+    builder.bucket(bucket).root(root);
+    Ok(ObjectStore::new(builder)
+        .context(crate::error::OpenDalSnafu)?
+        .finish())
+}
+
+#[cfg(feature = "storage-oss")]
+fn build_oss(authority_and_path: &str) -> Result<ObjectStore> {
+    use object_store::services::Oss;
+    use snafu::ResultExt;
+
+    let (bucket, root) = authority_and_path
+        .split_once('/')
+        .unwrap_or((authority_and_path, ""));
+    let mut builder = Oss::default();
+    builder.bucket(bucket).root(root);
+    Ok(ObjectStore::new(builder)
+        .context(crate::error::OpenDalSnafu)?
+        .finish())
+}
+
+#[cfg(feature = "storage-gcs")]
+fn build_gcs(authority_and_path: &str) -> Result<ObjectStore> {
+    use object_store::services::Gcs;
+    use snafu::ResultExt;
+
+    let (bucket, root) = authority_and_path
+        .split_once('/')
+        .unwrap_or((authority_and_path, ""));
+    let mut builder = Gcs::default();
+    builder.bucket(bucket).root(root);
+    Ok(ObjectStore::new(builder)
+        .context(crate::error::OpenDalSnafu)?
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_defaults_without_storage() {
+        let registry = StorageRegistry::default();
+        let default = build_memory();
+        let resolved = registry.resolve(None, &default).unwrap();
+        assert_eq!(format!("{resolved:?}"), format!("{default:?}"));
+    }
+
+    #[test]
+    fn test_resolve_by_url_scheme() {
+        let registry = StorageRegistry::default();
+        let default = build_memory();
+        assert!(registry.resolve(Some("memory://"), &default).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_storage_fails() {
+        let registry = StorageRegistry::default();
+        let default = build_memory();
+        assert!(registry
+            .resolve(Some("not-a-registered-name"), &default)
+            .is_err());
+    }
+}